@@ -62,6 +62,32 @@ class ProxyService : VpnService() {
         external fun clearCache()
         @JvmStatic
         external fun clearLogs()
+        @JvmStatic
+        external fun invalidateDomain(domain: String)
+        @JvmStatic
+        external fun setLogLevel(level: String)
+        @JvmStatic
+        external fun setQueryCallbackEnabled(enabled: Boolean)
+        @JvmStatic
+        external fun setSystemDns(servers: String)
+        @JvmStatic
+        external fun setCacheTtlBounds(min: Long, max: Long): Boolean
+        @JvmStatic
+        external fun getStatusJson(): String
+        @JvmStatic
+        external fun getUpstreamIps(): Array<String>
+        @JvmStatic
+        external fun getQueryTypeCounts(): String
+        @JvmStatic
+        external fun onNetworkChanged()
+        @JvmStatic
+        external fun setBlocklist(domains: Array<String>)
+        @JvmStatic
+        external fun getBlocklist(): Array<String>
+
+        /** Receives each resolved query while the native callback is enabled. */
+        @Volatile
+        var queryEventListener: ((domain: String, status: String, latencyMs: Int) -> Unit)? = null
 
         @JvmStatic
         fun nativeLog(level: String, tag: String, message: String) {
@@ -73,6 +99,11 @@ class ProxyService : VpnService() {
             }
         }
 
+        @JvmStatic
+        fun onQueryEvent(domain: String, status: String, latencyMs: Int) {
+            queryEventListener?.invoke(domain, status, latencyMs)
+        }
+
         init {
             System.loadLibrary("https_dns_proxy_rust")
         }
@@ -94,10 +125,24 @@ class ProxyService : VpnService() {
     private external fun stopProxy()
 
     private var connectivityManager: android.net.ConnectivityManager? = null
+    private var currentNetwork: android.net.Network? = null
     private val networkCallback = object : android.net.ConnectivityManager.NetworkCallback() {
         override fun onAvailable(network: android.net.Network) {
             super.onAvailable(network)
             if (BuildConfig.DEBUG) Log.d(TAG, "Network available")
+            // The first callback only reports the network we started on
+            val previous = currentNetwork
+            currentNetwork = network
+            if (previous != null && previous != network) onNetworkChanged()
+        }
+
+        override fun onLinkPropertiesChanged(network: android.net.Network, linkProperties: android.net.LinkProperties) {
+            super.onLinkPropertiesChanged(network, linkProperties)
+            // Skip our own virtual DNS addresses when the VPN is the default network
+            val servers = linkProperties.dnsServers
+                .mapNotNull { it.hostAddress }
+                .filter { it != "10.0.0.2" && it != "fd00::2" }
+            setSystemDns(servers.joinToString(","))
         }
     }
 
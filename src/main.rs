@@ -9,12 +9,12 @@ use nix::unistd::{User, Group, setuid, setgid};
 #[cfg(not(target_os = "android"))]
 use daemonize::Daemonize;
 use std::fs::File;
-use https_dns_proxy_rust::{Config, Stats, run_proxy};
+use https_dns_proxy_rust::{BlockResponse, BootstrapPending, BootstrapProtocol, ClassRejection, Config, IpStrategy, RootQueryMode, SizeRollingFile, Stats, run_proxy, set_cache_ttl_bounds, upstream_ips};
 
 #[derive(Parser, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Local IPv4/v6 address to bind to
+    /// Local IPv4/v6 address or hostname to bind to
     #[arg(short = 'a', long, default_value = "127.0.0.1")]
     listen_addr: String,
 
@@ -34,10 +34,14 @@ struct Args {
     #[arg(short = 'i', long, default_value_t = 120)]
     polling_interval: u64,
 
-    /// Force IPv4 hostnames for DNS resolvers
+    /// Force IPv4 hostnames for DNS resolvers (same as --ip-strategy ipv4-only)
     #[arg(short = '4', long)]
     force_ipv4: bool,
 
+    /// Address families to bootstrap the resolver host to (ipv4-only, ipv6-only, ipv4-then-ipv6 or ipv6-then-ipv4)
+    #[arg(long, default_value = "ipv4-then-ipv6")]
+    ip_strategy: IpStrategy,
+
     /// The HTTPS path to the resolver URL
     #[arg(short = 'r', long, default_value = "https://cloudflare-dns.com/dns-query")]
     resolver_url: String,
@@ -46,7 +50,7 @@ struct Args {
     #[arg(short = 't', long)]
     proxy_server: Option<String>,
 
-    /// Source IPv4/v6 address for outbound HTTPS connections
+    /// Source IPv4/v6 address for outbound HTTPS connections, IPv6 optionally with a zone (fe80::1%wlan0); a comma-separated list is used in turn
     #[arg(short = 'S', long)]
     source_addr: Option<String>,
 
@@ -54,7 +58,7 @@ struct Args {
     #[arg(short = 'x', long)]
     http11: bool,
 
-    /// Use HTTP/3 (QUIC) only
+    /// Use HTTP/3 (QUIC) only. Sessions don't migrate across network changes; they are reopened
     #[arg(short = 'q', long)]
     http3: bool,
 
@@ -86,10 +90,22 @@ struct Args {
     #[arg(short = 'v', long, action = clap::ArgAction::Count)]
     verbose: u8,
 
+    /// Send log output to the local syslog daemon
+    #[arg(long)]
+    syslog: bool,
+
     /// Path to file to log to
     #[arg(short = 'l', long)]
     logfile: Option<String>,
 
+    /// Rotate the logfile once it exceeds this many megabytes (0 = never)
+    #[arg(long, default_value_t = 0)]
+    log_max_size: u64,
+
+    /// Number of rotated logfiles to keep alongside the active one
+    #[arg(long, default_value_t = 5)]
+    log_max_files: usize,
+
     /// Optional statistic printout interval
     #[arg(short = 's', long, default_value_t = 0)]
     statistic_interval: u64,
@@ -102,6 +118,375 @@ struct Args {
     #[arg(short = 'e', long)]
     exclude_domain: Option<String>,
 
+    /// NAT64 prefix used to reach IPv4 bootstrap servers on IPv6-only networks
+    #[arg(long, default_value = "64:ff9b::/96")]
+    nat64_prefix: String,
+
+    /// Source port range for plaintext UDP bootstrap queries, e.g. 20000-30000 (empty = random ephemeral ports)
+    #[arg(long, default_value = "")]
+    bootstrap_port_range: String,
+
+    /// Optional MaxMind mmdb file used to log the country/ASN of the resolver endpoint
+    #[arg(long)]
+    geoip_db: Option<String>,
+
+    /// Validate DNSSEC signatures locally for queries with the DO bit set
+    #[arg(long)]
+    validate_dnssec: bool,
+
+    /// Optional file of DNSKEY records to use as the DNSSEC trust anchor
+    #[arg(long)]
+    dnssec_trust_anchor: Option<String>,
+
+    /// Seconds an idle TCP client connection is kept open for further queries
+    #[arg(long, default_value_t = 10)]
+    tcp_idle_timeout: u64,
+
+    /// Maximum concurrent upstream DoH requests, excess requests queue (0 = unlimited)
+    #[arg(long, default_value_t = 0)]
+    max_inflight_upstream: usize,
+
+    /// Do not listen for UDP queries
+    #[arg(long)]
+    disable_udp: bool,
+
+    /// Do not listen for TCP queries
+    #[arg(long)]
+    disable_tcp: bool,
+
+    /// Randomize the order of A/AAAA records in every response
+    #[arg(long)]
+    shuffle_answers: bool,
+
+    /// Reject TCP clients beyond the client limit instead of queuing them
+    #[arg(long)]
+    tcp_limit_reject: bool,
+
+    /// Largest accepted query in bytes, bigger queries get a FORMERR response
+    #[arg(long, default_value_t = 4096)]
+    max_query_size: usize,
+
+    /// Blocklist of domains to answer with NXDOMAIN, as a file path or http(s) URL
+    #[arg(long)]
+    blocklist_file: Option<String>,
+
+    /// Seconds between blocklist reloads (0 = load once)
+    #[arg(long, default_value_t = 86400)]
+    blocklist_refresh_interval: u64,
+
+    /// Bootstrap with the system's configured DNS servers, falling back to --bootstrap-dns
+    #[arg(long)]
+    use_system_bootstrap: bool,
+
+    /// Answer reverse lookups for private, loopback and link-local addresses locally with NXDOMAIN
+    #[arg(long)]
+    block_private_ptr: bool,
+
+    /// Lower bound in seconds applied to cached response TTLs
+    #[arg(long, default_value_t = 10)]
+    cache_min_ttl: u64,
+
+    /// Upper bound in seconds applied to cached response TTLs
+    #[arg(long, default_value_t = 3600)]
+    cache_max_ttl: u64,
+
+    /// Optional file holding "<min> <max>" cache TTL bounds, re-read on SIGHUP
+    #[arg(long)]
+    cache_ttl_bounds_file: Option<String>,
+
+    /// Name answered locally with a TXT record describing the upstream state, e.g. whoami.dnsproxy.local.
+    /// Any client reaching the listener can read the resolver and upstream details (empty = disabled)
+    #[arg(long, default_value = "")]
+    diagnostic_name: String,
+
+    /// Protocol used to reach the bootstrap DNS servers (udp, tcp or dot)
+    #[arg(long, default_value = "udp")]
+    bootstrap_protocol: BootstrapProtocol,
+
+    /// Consecutive upstream failures that open the circuit breaker (0 = disabled)
+    #[arg(long, default_value_t = 0)]
+    breaker_threshold: usize,
+
+    /// Seconds the circuit stays open before a probe request is let through
+    #[arg(long, default_value_t = 30)]
+    breaker_cooldown: u64,
+
+    /// TTL in seconds of the SOA record attached to locally generated negative answers
+    #[arg(long, default_value_t = 60)]
+    synthetic_ttl: u32,
+
+    /// Optional PEM client certificate chain for DoH servers requiring mutual TLS
+    #[arg(long)]
+    client_cert_path: Option<String>,
+
+    /// Optional PEM private key matching --client-cert-path
+    #[arg(long)]
+    client_key_path: Option<String>,
+
+    /// Randomize the letter case of bootstrap queries and require answers to echo it (0x20 encoding)
+    #[arg(long)]
+    bootstrap_0x20: bool,
+
+    /// Answer queries outside the IN and CH classes locally (off, notimp or refused)
+    #[arg(long, default_value = "off")]
+    reject_non_in_class: ClassRejection,
+
+    /// Optional addr:port on which to also serve DoH (RFC 8484, HTTP/2) to clients
+    #[arg(long)]
+    doh_listen_addr: Option<String>,
+
+    /// PEM certificate chain for the DoH listener
+    #[arg(long)]
+    doh_cert_path: Option<String>,
+
+    /// PEM private key for the DoH listener
+    #[arg(long)]
+    doh_key_path: Option<String>,
+
+    /// Randomize each bootstrap polling interval by up to this many percent either way
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    polling_jitter: u8,
+
+    /// Optional second DoH URL that receives copies of queries to log answer differences
+    #[arg(long)]
+    mirror_resolver: Option<String>,
+
+    /// Percentage of upstream queries copied to the mirror resolver
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(0..=100))]
+    mirror_sample_percent: u8,
+
+    /// Seconds to wait for each bootstrap server before trying the next one
+    #[arg(long, default_value_t = 5)]
+    bootstrap_timeout: u64,
+
+    /// Bootstrap servers queried in parallel, the first answer wins
+    #[arg(long, default_value_t = 2)]
+    bootstrap_concurrency: usize,
+
+    /// Keep connecting to the first resolver IP that answered until it fails
+    #[arg(long)]
+    pin_resolver_ip: bool,
+
+    /// Follow HTTP redirects from the resolver instead of treating them as errors
+    #[arg(long)]
+    follow_redirects: bool,
+
+    /// Answer AAAA queries with NODATA so clients fall back to IPv4
+    #[arg(long)]
+    filter_aaaa: bool,
+
+    /// Directory for a second cache tier holding entries evicted from memory
+    #[arg(long)]
+    disk_cache_dir: Option<String>,
+
+    /// Maximum number of entries kept in the disk cache tier
+    #[arg(long, default_value_t = 10000)]
+    disk_cache_entries: usize,
+
+    /// Answer for blocked domains (nxdomain, null or refused)
+    #[arg(long, default_value = "nxdomain")]
+    block_response_mode: BlockResponse,
+
+    /// Pad every response to at least this many milliseconds (0 = off)
+    #[arg(long, default_value_t = 0)]
+    min_response_delay_ms: u64,
+
+    /// Advertise the TCP idle timeout to clients via EDNS TCP keepalive (RFC 7828)
+    #[arg(long)]
+    edns_tcp_keepalive: bool,
+
+    /// Per-type cache TTL caps overriding the global maximum, e.g. TXT=300,SOA=86400
+    #[arg(long, default_value = "")]
+    type_max_ttl: String,
+
+    /// Additional DoH resolver URLs, comma separated, used when the primary fails
+    #[arg(long, default_value = "")]
+    extra_resolvers: String,
+
+    /// Prefer the resolver with the lowest measured latency, occasionally probing the others
+    #[arg(long)]
+    latency_aware: bool,
+
+    /// Disable per-query logging (query log and per-query debug lines)
+    #[arg(long)]
+    quiet: bool,
+
+    /// URL probed for a captive portal; expects an empty 204 (e.g. http://connectivitycheck.gstatic.com/generate_204)
+    #[arg(long)]
+    captive_portal_url: Option<String>,
+
+    /// Seconds between captive portal probes
+    #[arg(long, default_value_t = 30)]
+    captive_portal_interval: u64,
+
+    /// Honor NAME.t<seconds>.dnsproxy queries that set the upstream timeout for one lookup
+    #[arg(long)]
+    timeout_suffix: bool,
+
+    /// Serve a read-only admin endpoint listing cache entries at GET /cache and streaming query events as NDJSON at GET /queries (PORT binds to loopback)
+    #[arg(long)]
+    admin_addr: Option<String>,
+
+    /// HTTP versions to try in order, e.g. h3,h2,h1 (empty = derive from --http11)
+    #[arg(long, default_value = "")]
+    http_version_preference: String,
+
+    /// Append observed A/AAAA answers for --learn-zone-domains to this hosts-format file
+    #[arg(long)]
+    learn_zone_file: Option<String>,
+
+    /// Comma-separated domains (and their subdomains) recorded into --learn-zone-file
+    #[arg(long, default_value = "")]
+    learn_zone_domains: String,
+
+    /// Handling of root-name (.) probes: forward, local or refused
+    #[arg(long, default_value = "forward")]
+    root_query_mode: RootQueryMode,
+
+    /// Write one line per query (time client domain qtype rcode latency cache) to this file
+    #[arg(long)]
+    query_log_file: Option<String>,
+
+    /// Rotate the query log once it exceeds this many megabytes (0 = never)
+    #[arg(long, default_value_t = 0)]
+    query_log_max_size: u64,
+
+    /// Number of rotated query logs to keep alongside the active one
+    #[arg(long, default_value_t = 5)]
+    query_log_max_files: usize,
+
+    /// Accept http:// resolver URLs, sending queries in plaintext (testing only)
+    #[arg(long)]
+    allow_insecure_resolver: bool,
+
+    /// Seconds before the first bootstrap refresh (0 = one polling interval)
+    #[arg(long, default_value_t = 0)]
+    bootstrap_initial_delay: u64,
+
+    /// Local HTTPS/SVCB answers as name=TYPE rdata entries separated by ';', e.g. example.com=HTTPS 1 . alpn=h2,h3
+    #[arg(long, default_value = "")]
+    local_svcb: String,
+
+    /// Attempts per bootstrap server before the lookup fails over
+    #[arg(long, default_value_t = 2)]
+    bootstrap_attempts: usize,
+
+    /// Never cache responses whose minimum answer TTL is 0 instead of raising it to --cache-min-ttl
+    #[arg(long)]
+    respect_zero_ttl: bool,
+
+    /// Share one upstream request between identical queries in flight, holding the answer this many ms (0 = off)
+    #[arg(long, default_value_t = 0)]
+    coalesce_window_ms: u64,
+
+    /// Pad TCP and DoH responses to this block size for clients that send EDNS padding (0 = off, 468 recommended)
+    #[arg(long, default_value_t = 0)]
+    response_padding_block: usize,
+
+    /// Remove RRSIG/NSEC/NSEC3 records from answers to queries without the DO bit
+    #[arg(long)]
+    strip_dnssec_for_nondo: bool,
+
+    /// Parallel HTTP/2 (and HTTP/3) connections to the resolver, opened at startup and used in turn
+    #[arg(long, default_value_t = 1)]
+    h2_connections: usize,
+
+    /// Listeners as comma-separated udp://addr:port?rcvbuf=N&sndbuf=N or tcp://addr:port?backlog=N&rcvbuf=N&sndbuf=N URLs, replacing --listen-addr/--listen-port
+    #[arg(long, default_value = "")]
+    listeners: String,
+
+    /// Start answering from the persisted disk cache before the resolver has bootstrapped; misses wait for the bootstrap to finish
+    #[arg(long)]
+    serve_cache_during_bootstrap: bool,
+
+    /// Return SERVFAIL straight away when the upstream connection fails because there is no network, instead of retrying
+    #[arg(long)]
+    fast_fail_offline: bool,
+
+    /// Comma-separated IPs blocked A/AAAA queries resolve to, e.g. a local blank-page server (overrides --block-response-mode)
+    #[arg(long, default_value = "")]
+    sinkhole_ip: String,
+
+    /// Answer identical queries pipelined on one TCP connection from a single upstream lookup
+    #[arg(long)]
+    tcp_pipeline_dedup: bool,
+
+    /// Maximum outstanding queries per client IP; further UDP queries are dropped and TCP connections closed (0 = unlimited)
+    #[arg(long, default_value_t = 0)]
+    per_client_inflight_limit: usize,
+
+    /// File with one bootstrap server (ip[:port]) per line, used along with --bootstrap-dns
+    #[arg(long)]
+    bootstrap_file: Option<String>,
+
+    /// Log the cache's entry count and estimated memory use every this many seconds (0 = off)
+    #[arg(long, default_value_t = 0)]
+    cache_report_interval: u64,
+
+    /// Bound the in-memory cache by total key and response bytes instead of 2048 entries (0 = entry count)
+    #[arg(long, default_value_t = 0)]
+    cache_max_bytes: u64,
+
+    /// Accept header sent with DoH requests, e.g. "application/dns-message, */*" for resolvers that reject the default
+    #[arg(long, default_value = "application/dns-message")]
+    accept_header: String,
+
+    /// Answer from "query_hex response_hex" pairs in this file instead of contacting upstream, for tests and offline demos
+    #[arg(long)]
+    mock_responses_file: Option<String>,
+
+    /// Comma-separated query types (e.g. ANY,DNSKEY) answered over UDP to non-loopback clients with an empty truncated response, forcing TCP
+    #[arg(long, default_value = "")]
+    amplification_guard_types: String,
+
+    /// Truncate UDP responses larger than this many bytes to non-loopback clients so they retry over TCP (0 = off)
+    #[arg(long, default_value_t = 0)]
+    amplification_guard_size: usize,
+
+    /// Answer from expired cache entries when every upstream attempt fails, instead of SERVFAIL
+    #[arg(long)]
+    serve_stale_on_error: bool,
+
+    /// How long past expiry a cache entry may still be served by --serve-stale-on-error, in seconds
+    #[arg(long, default_value_t = 86400)]
+    max_stale_ttl: u64,
+
+    /// Answer REFUSED to queries with the recursion desired bit cleared, since the proxy only offers recursion
+    #[arg(long)]
+    require_rd: bool,
+
+    /// Keep retrying on a client whose connection was closed instead of replacing it with a fresh one
+    #[arg(long)]
+    disable_connection_renewal: bool,
+
+    /// Run upstream HTTPS requests on a dedicated runtime with this many worker threads, so TLS handshakes don't stall local serving (0 = share the main runtime)
+    #[arg(long, default_value_t = 0)]
+    upstream_threads: usize,
+
+    /// Answer for cache misses while a deferred bootstrap is still running: servfail, queue (wait for it) or cache (serve expired entries)
+    #[arg(long, default_value = "servfail")]
+    bootstrap_pending_behavior: BootstrapPending,
+
+    /// How long a query may wait for bootstrap with --bootstrap-pending-behavior queue, in milliseconds
+    #[arg(long, default_value_t = 5000)]
+    bootstrap_queue_timeout_ms: u64,
+
+    /// Collapse identical records in upstream answer sections before caching and answering
+    #[arg(long)]
+    dedup_answers: bool,
+
+    /// Resolve the DoH provider hostname through this DoH URL instead of plaintext bootstrap DNS (requires --bootstrap-doh-ip)
+    #[arg(long)]
+    bootstrap_doh_url: Option<String>,
+
+    /// IP address the --bootstrap-doh-url host is reached at
+    #[arg(long)]
+    bootstrap_doh_ip: Option<String>,
+
+    /// Drop expired cache entries and flush moka's pending maintenance every this many seconds (0 = off)
+    #[arg(long, default_value_t = 0)]
+    cache_cleanup_interval: u64,
+
     /// Print versions and exit
     #[arg(short = 'P', long)]
     print_version: bool,
@@ -116,7 +501,7 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    setup_logging(args.verbose, &args.logfile);
+    setup_logging(args.verbose, &args.logfile, args.log_max_size, args.log_max_files, args.syslog);
 
     #[cfg(not(target_os = "android"))]
     if args.daemonize {
@@ -137,8 +522,7 @@ async fn main() -> Result<()> {
         tcp_client_limit: args.tcp_client_limit,
         bootstrap_dns: args.bootstrap_dns,
         polling_interval: args.polling_interval,
-        force_ipv4: args.force_ipv4,
-        allow_ipv6: !args.force_ipv4,
+        ip_strategy: if args.force_ipv4 { IpStrategy::Ipv4Only } else { args.ip_strategy },
         resolver_url: args.resolver_url,
         proxy_server: args.proxy_server,
         source_addr: args.source_addr,
@@ -150,9 +534,108 @@ async fn main() -> Result<()> {
         statistic_interval: args.statistic_interval,
         cache_ttl: args.cache_ttl,
         exclude_domain: args.exclude_domain,
+        nat64_prefix: args.nat64_prefix,
+        bootstrap_port_range: args.bootstrap_port_range,
+        geoip_db: args.geoip_db,
+        validate_dnssec: args.validate_dnssec,
+        dnssec_trust_anchor: args.dnssec_trust_anchor,
+        tcp_idle_timeout: args.tcp_idle_timeout,
+        max_inflight_upstream: args.max_inflight_upstream,
+        disable_udp: args.disable_udp,
+        disable_tcp: args.disable_tcp,
+        shuffle_answers: args.shuffle_answers,
+        tcp_limit_reject: args.tcp_limit_reject,
+        max_query_size: args.max_query_size,
+        blocklist_file: args.blocklist_file,
+        blocklist_refresh_interval: args.blocklist_refresh_interval,
+        use_system_bootstrap: args.use_system_bootstrap,
+        block_private_ptr: args.block_private_ptr,
+        cache_min_ttl: args.cache_min_ttl,
+        cache_max_ttl: args.cache_max_ttl,
+        diagnostic_name: args.diagnostic_name,
+        bootstrap_protocol: args.bootstrap_protocol,
+        breaker_threshold: args.breaker_threshold,
+        breaker_cooldown: args.breaker_cooldown,
+        synthetic_ttl: args.synthetic_ttl,
+        client_cert_path: args.client_cert_path,
+        client_key_path: args.client_key_path,
+        bootstrap_0x20: args.bootstrap_0x20,
+        reject_non_in_class: args.reject_non_in_class,
+        doh_listen_addr: args.doh_listen_addr,
+        doh_cert_path: args.doh_cert_path,
+        doh_key_path: args.doh_key_path,
+        polling_jitter: args.polling_jitter,
+        mirror_resolver: args.mirror_resolver,
+        mirror_sample_percent: args.mirror_sample_percent,
+        bootstrap_timeout: args.bootstrap_timeout,
+        bootstrap_concurrency: args.bootstrap_concurrency,
+        pin_resolver_ip: args.pin_resolver_ip,
+        follow_redirects: args.follow_redirects,
+        filter_aaaa: args.filter_aaaa,
+        disk_cache_dir: args.disk_cache_dir,
+        disk_cache_entries: args.disk_cache_entries,
+        block_response_mode: args.block_response_mode,
+        min_response_delay_ms: args.min_response_delay_ms,
+        edns_tcp_keepalive: args.edns_tcp_keepalive,
+        type_max_ttl: args.type_max_ttl,
+        extra_resolvers: args.extra_resolvers,
+        latency_aware: args.latency_aware,
+        quiet: args.quiet,
+        captive_portal_url: args.captive_portal_url,
+        captive_portal_interval: args.captive_portal_interval,
+        timeout_suffix: args.timeout_suffix,
+        admin_addr: args.admin_addr,
+        http_version_preference: args.http_version_preference,
+        learn_zone_file: args.learn_zone_file,
+        learn_zone_domains: args.learn_zone_domains,
+        root_query_mode: args.root_query_mode,
+        query_log_file: args.query_log_file,
+        query_log_max_size: args.query_log_max_size,
+        query_log_max_files: args.query_log_max_files,
+        allow_insecure_resolver: args.allow_insecure_resolver,
+        bootstrap_initial_delay: args.bootstrap_initial_delay,
+        local_svcb: args.local_svcb,
+        bootstrap_attempts: args.bootstrap_attempts,
+        respect_zero_ttl: args.respect_zero_ttl,
+        coalesce_window_ms: args.coalesce_window_ms,
+        response_padding_block: args.response_padding_block,
+        strip_dnssec_for_nondo: args.strip_dnssec_for_nondo,
+        h2_connections: args.h2_connections,
+        listeners: args.listeners,
+        serve_cache_during_bootstrap: args.serve_cache_during_bootstrap,
+        fast_fail_offline: args.fast_fail_offline,
+        sinkhole_ip: args.sinkhole_ip,
+        tcp_pipeline_dedup: args.tcp_pipeline_dedup,
+        per_client_inflight_limit: args.per_client_inflight_limit,
+        bootstrap_file: args.bootstrap_file,
+        cache_report_interval: args.cache_report_interval,
+        cache_max_bytes: args.cache_max_bytes,
+        accept_header: args.accept_header,
+        mock_responses_file: args.mock_responses_file,
+        amplification_guard_types: args.amplification_guard_types,
+        amplification_guard_size: args.amplification_guard_size,
+        serve_stale_on_error: args.serve_stale_on_error,
+        max_stale_ttl: args.max_stale_ttl,
+        require_rd: args.require_rd,
+        disable_connection_renewal: args.disable_connection_renewal,
+        upstream_threads: args.upstream_threads,
+        bootstrap_pending_behavior: args.bootstrap_pending_behavior,
+        bootstrap_queue_timeout_ms: args.bootstrap_queue_timeout_ms,
+        dedup_answers: args.dedup_answers,
+        bootstrap_doh_url: args.bootstrap_doh_url,
+        bootstrap_doh_ip: args.bootstrap_doh_ip,
+        cache_cleanup_interval: args.cache_cleanup_interval,
     };
 
+    #[cfg(unix)]
+    if let Some(path) = args.cache_ttl_bounds_file.clone() {
+        spawn_ttl_bounds_reloader(path)?;
+    }
+
     let stats = Arc::new(Stats::new());
+
+    #[cfg(unix)]
+    spawn_upstream_reporter(stats.clone())?;
     let (_shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
 
     run_proxy(config, stats, shutdown_rx).await?;
@@ -160,7 +643,52 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn setup_logging(verbosity: u8, logfile: &Option<String>) {
+/// Logs the resolver addresses in use and the per-type query counts every time the
+/// process receives SIGUSR1.
+#[cfg(unix)]
+fn spawn_upstream_reporter(stats: Arc<Stats>) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut usr1 = signal(SignalKind::user_defined1()).context("Failed to install SIGUSR1 handler")?;
+    tokio::spawn(async move {
+        while usr1.recv().await.is_some() {
+            let ips = upstream_ips().await;
+            tracing::info!("Upstream IPs: {:?}", ips);
+            let counts: Vec<String> = stats.query_type_counts().iter().map(|(t, n)| format!("{}={}", t, n)).collect();
+            tracing::info!("Query types: {}", counts.join(" "));
+        }
+    });
+    Ok(())
+}
+
+/// Applies the TTL bounds file every time the process receives SIGHUP.
+#[cfg(unix)]
+fn spawn_ttl_bounds_reloader(path: String) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup()).context("Failed to install SIGHUP handler")?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match load_ttl_bounds(&path).and_then(|(min, max)| set_cache_ttl_bounds(min, max).map(|_| (min, max))) {
+                Ok((min, max)) => tracing::info!("Cache TTL bounds set to {}..{}s", min, max),
+                Err(e) => tracing::warn!("Keeping previous cache TTL bounds: {:#}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(unix)]
+fn load_ttl_bounds(path: &str) -> Result<(u64, u64)> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let mut values = text.split_whitespace().map(str::parse::<u64>);
+    match (values.next(), values.next()) {
+        (Some(Ok(min)), Some(Ok(max))) => Ok((min, max)),
+        _ => Err(anyhow::anyhow!("Expected \"<min> <max>\" in {}", path)),
+    }
+}
+
+fn setup_logging(verbosity: u8, logfile: &Option<String>, log_max_size: u64, log_max_files: usize, syslog: bool) {
     let level = match verbosity {
         0 => Level::INFO,
         1 => Level::DEBUG,
@@ -172,6 +700,40 @@ fn setup_logging(verbosity: u8, logfile: &Option<String>) {
 
     let registry = tracing_subscriber::registry().with(filter);
 
+    #[cfg(unix)]
+    if syslog {
+        match syslog::SyslogMakeWriter::connect() {
+            Ok(writer) => {
+                let layer = tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_ansi(false)
+                    .without_time();
+                registry.with(layer).init();
+                return;
+            }
+            Err(e) => eprintln!("Failed to connect to syslog, logging to stderr: {}", e),
+        }
+    }
+    #[cfg(not(unix))]
+    if syslog {
+        eprintln!("Syslog is only supported on Unix, logging to stderr");
+    }
+
+    if let Some(path) = logfile
+        && log_max_size > 0
+    {
+        match SizeRollingFile::open(path, log_max_size * 1024 * 1024, log_max_files) {
+            Ok(file) => {
+                let layer = tracing_subscriber::fmt::layer()
+                    .with_writer(std::sync::Mutex::new(file))
+                    .with_ansi(false);
+                registry.with(layer).init();
+                return;
+            }
+            Err(e) => eprintln!("Failed to open logfile {}: {}", path, e),
+        }
+    }
+
     if let Some(path) = logfile {
         if let Ok(file) = File::create(path) {
             let layer = tracing_subscriber::fmt::layer()
@@ -200,3 +762,107 @@ fn drop_privileges(user_name: &Option<String>, group_name: &Option<String>) -> R
     }
     Ok(())
 }
+
+/// Minimal RFC 3164 client writing to the local syslog socket, one datagram per event.
+#[cfg(unix)]
+mod syslog {
+    use std::io::{self, Write};
+    use std::os::unix::net::UnixDatagram;
+    use std::sync::Arc;
+    use tracing::{Level, Metadata};
+    use tracing_subscriber::fmt::MakeWriter;
+
+    const FACILITY_DAEMON: u8 = 3;
+
+    pub struct SyslogMakeWriter {
+        socket: Arc<UnixDatagram>,
+    }
+
+    impl SyslogMakeWriter {
+        pub fn connect() -> io::Result<Self> {
+            let socket = UnixDatagram::unbound()?;
+            socket.connect("/dev/log").or_else(|_| socket.connect("/var/run/syslog"))?;
+            Ok(Self { socket: Arc::new(socket) })
+        }
+
+        fn writer(&self, severity: u8) -> SyslogWriter {
+            SyslogWriter {
+                socket: self.socket.clone(),
+                severity,
+                buf: Vec::new(),
+            }
+        }
+    }
+
+    pub struct SyslogWriter {
+        socket: Arc<UnixDatagram>,
+        severity: u8,
+        buf: Vec<u8>,
+    }
+
+    impl Write for SyslogWriter {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.buf.extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Drop for SyslogWriter {
+        fn drop(&mut self) {
+            let msg = String::from_utf8_lossy(&self.buf);
+            let msg = msg.trim();
+            if msg.is_empty() {
+                return;
+            }
+            let line = format!(
+                "<{}>https_dns_proxy[{}]: {}",
+                FACILITY_DAEMON * 8 + self.severity,
+                std::process::id(),
+                msg
+            );
+            let _ = self.socket.send(line.as_bytes());
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for SyslogMakeWriter {
+        type Writer = SyslogWriter;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.writer(6)
+        }
+
+        fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+            let severity = match *meta.level() {
+                Level::ERROR => 3,
+                Level::WARN => 4,
+                Level::INFO => 6,
+                _ => 7,
+            };
+            self.writer(severity)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn events_are_sent_as_one_rfc3164_datagram() {
+            let (tx, rx) = UnixDatagram::pair().unwrap();
+            let make_writer = SyslogMakeWriter { socket: Arc::new(tx) };
+            let mut writer = make_writer.writer(4);
+            write!(writer, "upstream ").unwrap();
+            writeln!(writer, "timed out").unwrap();
+            drop(writer);
+
+            let mut buf = [0u8; 256];
+            let n = rx.recv(&mut buf).unwrap();
+            let expected = format!("<28>https_dns_proxy[{}]: upstream timed out", std::process::id());
+            assert_eq!(std::str::from_utf8(&buf[..n]).unwrap(), expected);
+        }
+    }
+}